@@ -0,0 +1,35 @@
+//! Proves the parser builds and runs with `std` compiled out.
+//!
+//! Build for a bare-metal target with the `std` feature disabled, e.g.:
+//! `cargo build --example no_std_smoke --no-default-features --target thumbv7em-none-eabihf`
+//!
+//! With the default (`std`) feature on, this is a no-op binary instead:
+//! `#![no_std]`/`#[panic_handler]` would otherwise collide with `std`'s own
+//! panic runtime when the example is picked up by a plain `cargo build
+//! --workspace`.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(feature = "std"), no_main)]
+
+#[cfg(not(feature = "std"))]
+use core::panic::PanicInfo;
+
+#[cfg(not(feature = "std"))]
+use tftp::packet::Packet;
+
+#[cfg(not(feature = "std"))]
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    let rrq = b"\x00\x01a\0octet\0";
+    let _ = Packet::parse(rrq);
+
+    loop {}
+}
+
+#[cfg(not(feature = "std"))]
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
+
+#[cfg(feature = "std")]
+fn main() {}
@@ -0,0 +1,125 @@
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::packet::{self, OwnedPacket, Packet};
+
+/// Frames a single UDP datagram as a [`OwnedPacket`] and back.
+///
+/// TFTP (RFC 1350) has no length-delimited streaming framing of its own:
+/// each datagram *is* one complete packet, so unlike a TCP codec this one
+/// does not need to buffer partial frames across calls. It is meant to be
+/// paired with `tokio_util::udp::UdpFramed` so a socket can be driven as a
+/// `Stream`/`Sink` of packets instead of raw bytes.
+#[derive(Debug, Default)]
+pub struct TftpCodec;
+
+/// Errors a [`TftpCodec`] can return, covering both its own parse failures
+/// and I/O errors `tokio_util` plumbs through from the underlying socket
+/// (`Decoder`/`Encoder` require `Error: From<std::io::Error>`).
+#[derive(Debug)]
+pub enum Error {
+    Parse(packet::Error),
+    Io(std::io::Error),
+}
+
+impl From<packet::Error> for Error {
+    fn from(error: packet::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "{error}"),
+            Error::Io(error) => write!(f, "I/O error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Decoder for TftpCodec {
+    type Item = OwnedPacket;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+
+        let packet = Packet::parse(src)?.into_owned();
+        src.clear();
+
+        Ok(Some(packet))
+    }
+}
+
+impl Encoder<OwnedPacket> for TftpCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: OwnedPacket, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&item.to_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use crate::options::Options;
+    use crate::packet::{Mode, OwnedPacket};
+
+    use super::TftpCodec;
+
+    #[test]
+    fn test_decode_then_encode_round_trips() {
+        let ack = &[0x00, 0x04, 0x00, 0x2A];
+        let mut src = BytesMut::from(&ack[..]);
+
+        let mut codec = TftpCodec;
+        let packet = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(packet, OwnedPacket::Ack { op_code: 4, block: 42 });
+        assert!(src.is_empty());
+
+        let mut dst = BytesMut::new();
+        codec.encode(packet, &mut dst).unwrap();
+        assert_eq!(&dst[..], ack);
+    }
+
+    #[test]
+    fn test_decode_empty_buffer_yields_none() {
+        let mut src = BytesMut::new();
+        let mut codec = TftpCodec;
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn test_encode_request() {
+        let packet = OwnedPacket::Request {
+            op_code: 1,
+            file_name: "main.rs".to_string(),
+            mode: Mode::Octet,
+            options: Options::default(),
+        };
+
+        let mut dst = BytesMut::new();
+        TftpCodec.encode(packet, &mut dst).unwrap();
+
+        assert_eq!(
+            &dst[..],
+            &[
+                0x00, 0x01, b'm', b'a', b'i', b'n', b'.', b'r', b's', 0x00, b'o', b'c', b't',
+                b'e', b't', 0x00
+            ]
+        );
+    }
+}
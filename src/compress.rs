@@ -0,0 +1,224 @@
+//! Negotiated DATA-stream compression via the experimental, non-RFC
+//! `compress` option (see [`crate::options::Compression`]).
+//!
+//! A peer that doesn't recognize `compress` rejects it like any other
+//! unknown option and the transfer falls back to plain, uncompressed
+//! octet mode; [`encode`]/[`decode`] are pass-through whenever no
+//! algorithm was negotiated. When both sides do agree, the sender runs
+//! [`encode`] once over the whole file and slices the result into DATA
+//! blocks; the receiver reassembles the blocks and runs [`decode`] once
+//! before writing the file out.
+//!
+//! Each algorithm lives behind its own Cargo feature (`compress-deflate`
+//! for `deflate`/`gzip`, `compress-zstd` for `zstd`) so the corresponding
+//! dependency is opt-in. Negotiating an algorithm whose feature isn't
+//! compiled in is an [`Error::Unsupported`], not a panic.
+
+use alloc::vec::Vec;
+
+use crate::options::Compression;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The negotiated algorithm's Cargo feature isn't compiled in.
+    Unsupported(Compression),
+    /// The compressed stream was truncated or otherwise malformed.
+    Corrupt,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Unsupported(algorithm) => {
+                write!(f, "compression algorithm not compiled in: {algorithm:?}")
+            }
+            Error::Corrupt => write!(f, "corrupt compressed stream"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Compresses `data` under the negotiated `algorithm`, or returns a copy
+/// of `data` unchanged if no algorithm was negotiated (`compress` was
+/// absent or rejected).
+pub fn encode(algorithm: Option<Compression>, data: &[u8]) -> Result<Vec<u8>, Error> {
+    match algorithm {
+        None => Ok(data.to_vec()),
+        Some(Compression::Deflate) => deflate::encode(data),
+        Some(Compression::Gzip) => gzip::encode(data),
+        Some(Compression::Zstd) => zstd_backend::encode(data),
+    }
+}
+
+/// Reverses [`encode`] over one fully reassembled DATA stream.
+pub fn decode(algorithm: Option<Compression>, data: &[u8]) -> Result<Vec<u8>, Error> {
+    match algorithm {
+        None => Ok(data.to_vec()),
+        Some(Compression::Deflate) => deflate::decode(data),
+        Some(Compression::Gzip) => gzip::decode(data),
+        Some(Compression::Zstd) => zstd_backend::decode(data),
+    }
+}
+
+#[cfg(feature = "compress-deflate")]
+mod deflate {
+    use std::io::{Read, Write};
+
+    use alloc::vec::Vec;
+
+    use super::Error;
+
+    pub(super) fn encode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).map_err(|_| Error::Corrupt)?;
+        encoder.finish().map_err(|_| Error::Corrupt)
+    }
+
+    pub(super) fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        flate2::read::DeflateDecoder::new(data)
+            .read_to_end(&mut out)
+            .map_err(|_| Error::Corrupt)?;
+        Ok(out)
+    }
+}
+
+#[cfg(not(feature = "compress-deflate"))]
+mod deflate {
+    use alloc::vec::Vec;
+
+    use super::{Compression, Error};
+
+    pub(super) fn encode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Deflate))
+    }
+
+    pub(super) fn decode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Deflate))
+    }
+}
+
+#[cfg(feature = "compress-deflate")]
+mod gzip {
+    use std::io::{Read, Write};
+
+    use alloc::vec::Vec;
+
+    use super::Error;
+
+    pub(super) fn encode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).map_err(|_| Error::Corrupt)?;
+        encoder.finish().map_err(|_| Error::Corrupt)
+    }
+
+    pub(super) fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(data)
+            .read_to_end(&mut out)
+            .map_err(|_| Error::Corrupt)?;
+        Ok(out)
+    }
+}
+
+#[cfg(not(feature = "compress-deflate"))]
+mod gzip {
+    use alloc::vec::Vec;
+
+    use super::{Compression, Error};
+
+    pub(super) fn encode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Gzip))
+    }
+
+    pub(super) fn decode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Gzip))
+    }
+}
+
+#[cfg(feature = "compress-zstd")]
+mod zstd_backend {
+    use alloc::vec::Vec;
+
+    use super::Error;
+
+    pub(super) fn encode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        zstd::stream::encode_all(data, 0).map_err(|_| Error::Corrupt)
+    }
+
+    pub(super) fn decode(data: &[u8]) -> Result<Vec<u8>, Error> {
+        zstd::stream::decode_all(data).map_err(|_| Error::Corrupt)
+    }
+}
+
+#[cfg(not(feature = "compress-zstd"))]
+mod zstd_backend {
+    use alloc::vec::Vec;
+
+    use super::{Compression, Error};
+
+    pub(super) fn encode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Zstd))
+    }
+
+    pub(super) fn decode(_data: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(Error::Unsupported(Compression::Zstd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_algorithm_is_pass_through() {
+        let data = b"hello world";
+        let encoded = encode(None, data).unwrap();
+        assert_eq!(encoded, data);
+        assert_eq!(decode(None, &encoded).unwrap(), data);
+    }
+
+    #[test]
+    #[cfg(feature = "compress-deflate")]
+    fn test_deflate_round_trips() {
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(64);
+        let encoded = encode(Some(Compression::Deflate), &data).unwrap();
+        assert!(encoded.len() < data.len());
+        assert_eq!(decode(Some(Compression::Deflate), &encoded).unwrap(), data);
+    }
+
+    #[test]
+    #[cfg(feature = "compress-deflate")]
+    fn test_gzip_round_trips() {
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(64);
+        let encoded = encode(Some(Compression::Gzip), &data).unwrap();
+        assert_eq!(decode(Some(Compression::Gzip), &encoded).unwrap(), data);
+    }
+
+    #[test]
+    #[cfg(feature = "compress-zstd")]
+    fn test_zstd_round_trips() {
+        let data = b"the quick brown fox jumps over the lazy dog".repeat(64);
+        let encoded = encode(Some(Compression::Zstd), &data).unwrap();
+        assert_eq!(decode(Some(Compression::Zstd), &encoded).unwrap(), data);
+    }
+
+    #[test]
+    #[cfg(not(feature = "compress-zstd"))]
+    fn test_unsupported_algorithm_falls_back_to_uncompressed() {
+        let data = b"hello world";
+
+        // The feature isn't compiled in, so a peer that negotiated zstd
+        // anyway must be told to fall back rather than silently losing
+        // data.
+        let err = encode(Some(Compression::Zstd), data).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(Compression::Zstd)));
+
+        let fallback = encode(None, data).unwrap();
+        assert_eq!(decode(None, &fallback).unwrap(), data);
+    }
+}
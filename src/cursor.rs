@@ -0,0 +1,28 @@
+//! A minimal `no_std`-friendly stand-in for `std::io::Cursor<&[u8]>`.
+//!
+//! Parsing only ever needs to track a read position into a borrowed byte
+//! slice, so it doesn't need `std::io::Read`/`BufReader` at all. Keeping
+//! this as its own tiny type (instead of pulling in `std::io`) is what lets
+//! `Packet::parse` and friends compile under `#![no_std]`.
+pub(crate) struct ByteCursor<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteCursor<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        ByteCursor { buf, pos: 0 }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(crate) fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    pub(crate) fn get_ref(&self) -> &'a [u8] {
+        self.buf
+    }
+}
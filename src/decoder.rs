@@ -0,0 +1,393 @@
+//! A synchronous, incremental packet decoder over any [`std::io::Read`].
+//!
+//! [`Packet::parse`] expects one complete datagram up front, which is
+//! natural when the source is a UDP `recv` (or the Tokio [`crate::codec`]
+//! built on top of one) but not when the source is a capture file, a
+//! pipe, or a reassembled stream where a single packet's bytes can arrive
+//! split across more than one `read`. [`Decoder`] buffers across calls
+//! and applies the same per-opcode layout `Packet::parse` uses internally
+//! to recognize when a full record has arrived: fixed 4 bytes for ACK,
+//! zero-byte-delimited fields for RRQ/WRQ/ERROR, and up to the negotiated
+//! `blksize` (with a short final block) for DATA.
+//!
+//! RFC 2347 options on RRQ/WRQ aren't self-delimiting in a raw byte
+//! stream (nothing marks where the last `option\0value\0` pair ends), so
+//! this decoder only frames the base RFC 1350 filename/mode fields; it's
+//! meant for replaying the DATA/ACK-heavy body of a transfer captured
+//! after option negotiation already happened over the packet-oriented
+//! path.
+
+use std::io::Read;
+use std::time::Duration;
+
+use crate::options::DEFAULT_BLKSIZE;
+use crate::packet::{self, OwnedPacket, Packet};
+
+/// How long [`Decoder::read_more`] sleeps between retries in `follow` mode
+/// after a zero-byte read, so tailing a not-yet-grown file or a FIFO with
+/// no writer attached doesn't spin a CPU core at 100%.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const ACK_OPCODE: u16 = 4;
+const DATA_OPCODE: u16 = 3;
+const ERROR_OPCODE: u16 = 5;
+const READ_OPCODE: u16 = 1;
+const WRITE_OPCODE: u16 = 2;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Not enough bytes were available to complete a record, and `follow`
+    /// was not set to wait for more. Recoverable in principle: reading
+    /// from the same position with more bytes appended would succeed.
+    Truncated { needed: usize, got: usize },
+    /// The underlying reader returned an I/O error.
+    Io(std::io::Error),
+    /// A complete record was read but did not parse as a valid TFTP
+    /// packet.
+    Parse(packet::Error),
+}
+
+impl From<packet::Error> for Error {
+    fn from(error: packet::Error) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Truncated { needed, got } => {
+                write!(f, "truncated record: needed {needed} bytes, got {got}")
+            }
+            Error::Io(error) => write!(f, "I/O error: {error}"),
+            Error::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Reads a sequence of TFTP datagrams out of `R` one at a time.
+pub struct Decoder<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Negotiated DATA block size (RFC 2348) for this transfer; bounds
+    /// how many payload bytes a DATA record may hold before the final,
+    /// possibly-shorter block. Defaults to [`DEFAULT_BLKSIZE`].
+    pub blksize: u16,
+    /// When set, [`Decoder::next_packet`] blocks (retrying reads) instead
+    /// of returning `Ok(None)` at EOF, for tailing a capture that's still
+    /// being appended to.
+    pub follow: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            reader,
+            buf: Vec::new(),
+            blksize: DEFAULT_BLKSIZE,
+            follow: false,
+        }
+    }
+
+    /// Reads and parses the next datagram.
+    ///
+    /// Returns `Ok(None)` at a clean EOF between records (only possible
+    /// when `follow` is `false`). A record that starts but is cut short
+    /// surfaces as `Err(Error::Truncated)`, distinct from
+    /// `Err(Error::Parse(_))`, which means a complete record was read but
+    /// its bytes were not a valid TFTP packet.
+    pub fn next_packet(&mut self) -> Result<Option<OwnedPacket>, Error> {
+        while self.buf.len() < 2 {
+            if !self.read_more()? {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                return Err(Error::Truncated {
+                    needed: 2,
+                    got: self.buf.len(),
+                });
+            }
+        }
+
+        let op_code = u16::from_be_bytes([self.buf[0], self.buf[1]]);
+        let len = match op_code {
+            ACK_OPCODE => self.frame_fixed(4)?,
+            ERROR_OPCODE => self.frame_zero_delimited(4, 1)?,
+            READ_OPCODE | WRITE_OPCODE => self.frame_zero_delimited(2, 2)?,
+            DATA_OPCODE => self.frame_data()?,
+            _ => {
+                // An unrecognized opcode has no framing rule to fall back
+                // on; surface it as a parse error on what we have so far.
+                return Err(packet::Error::InvalidOpcode.into());
+            }
+        };
+
+        let record: Vec<u8> = self.buf.drain(..len).collect();
+        let packet = Packet::parse(&record)?;
+        Ok(Some(packet.into_owned()))
+    }
+
+    /// Ensures a fixed-size record of `len` bytes (header included) is
+    /// buffered.
+    fn frame_fixed(&mut self, len: usize) -> Result<usize, Error> {
+        while self.buf.len() < len {
+            if !self.read_more()? {
+                return Err(Error::Truncated {
+                    needed: len,
+                    got: self.buf.len(),
+                });
+            }
+        }
+        Ok(len)
+    }
+
+    /// Ensures a record made of `header_len` fixed bytes followed by
+    /// `zero_count` zero-terminated fields is buffered, returning its
+    /// total length.
+    fn frame_zero_delimited(&mut self, header_len: usize, zero_count: usize) -> Result<usize, Error> {
+        while self.buf.len() < header_len {
+            if !self.read_more()? {
+                return Err(Error::Truncated {
+                    needed: header_len,
+                    got: self.buf.len(),
+                });
+            }
+        }
+
+        let mut end = header_len;
+        for _ in 0..zero_count {
+            end = self.find_zero_from(end)?;
+        }
+        Ok(end)
+    }
+
+    /// Grows the buffer until a zero byte is found at or after `from`,
+    /// returning the position just past it.
+    fn find_zero_from(&mut self, from: usize) -> Result<usize, Error> {
+        let mut scanned = from;
+        loop {
+            if let Some(pos) = self.buf[scanned..].iter().position(|&b| b == 0) {
+                return Ok(scanned + pos + 1);
+            }
+            scanned = self.buf.len();
+
+            if !self.read_more()? {
+                return Err(Error::Truncated {
+                    needed: scanned + 1,
+                    got: scanned,
+                });
+            }
+        }
+    }
+
+    /// Ensures a DATA record is buffered: the 4-byte header plus up to
+    /// `blksize` payload bytes, or fewer if the source ends first (the
+    /// final, short block of a transfer).
+    fn frame_data(&mut self) -> Result<usize, Error> {
+        let target = 4 + self.blksize as usize;
+        loop {
+            if self.buf.len() >= target {
+                return Ok(target);
+            }
+            if !self.read_more()? {
+                if self.buf.len() < 4 {
+                    return Err(Error::Truncated {
+                        needed: 4,
+                        got: self.buf.len(),
+                    });
+                }
+                return Ok(self.buf.len());
+            }
+        }
+    }
+
+    /// Reads one more chunk into the internal buffer. Returns `false` on
+    /// a clean EOF; in `follow` mode, keeps retrying (sleeping
+    /// [`FOLLOW_POLL_INTERVAL`] between zero-byte reads) instead of ever
+    /// returning `false`.
+    fn read_more(&mut self) -> Result<bool, Error> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = self.reader.read(&mut chunk).map_err(Error::Io)?;
+            if n > 0 {
+                self.buf.extend_from_slice(&chunk[..n]);
+                return Ok(true);
+            }
+            if !self.follow {
+                return Ok(false);
+            }
+            std::thread::sleep(FOLLOW_POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use crate::packet::OwnedPacket;
+
+    use super::{Decoder, Error};
+
+    /// A `Read` that hands back the bytes of `chunks` one slice at a time
+    /// per call, so tests can exercise a packet arriving split across
+    /// several reads.
+    struct StagedReader {
+        chunks: std::collections::VecDeque<Vec<u8>>,
+    }
+
+    impl StagedReader {
+        fn new(chunks: Vec<Vec<u8>>) -> Self {
+            StagedReader {
+                chunks: chunks.into_iter().collect(),
+            }
+        }
+    }
+
+    impl Read for StagedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some(chunk) = self.chunks.pop_front() else {
+                return Ok(0);
+            };
+            buf[..chunk.len()].copy_from_slice(&chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn test_decode_ack() {
+        let mut decoder = Decoder::new(std::io::Cursor::new(vec![0x00, 0x04, 0x00, 0x2A]));
+
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 42 })
+        );
+        assert_eq!(decoder.next_packet().unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_rrq() {
+        let mut rrq = vec![0x00, 0x01];
+        rrq.extend_from_slice(b"a\0octet\0");
+        let mut decoder = Decoder::new(std::io::Cursor::new(rrq));
+
+        match decoder.next_packet().unwrap().unwrap() {
+            OwnedPacket::Request { file_name, .. } => assert_eq!(file_name, "a"),
+            other => panic!("did not get expected packet: Request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let mut error = vec![0x00, 0x05, 0x00, 0x01];
+        error.extend_from_slice(b"not found\0");
+        let mut decoder = Decoder::new(std::io::Cursor::new(error));
+
+        match decoder.next_packet().unwrap().unwrap() {
+            OwnedPacket::Error { error_msg, .. } => assert_eq!(error_msg, "not found"),
+            other => panic!("did not get expected packet: Error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_multiple_packets_in_sequence() {
+        let mut bytes = vec![0x00, 0x04, 0x00, 0x01];
+        bytes.extend_from_slice(&[0x00, 0x04, 0x00, 0x02]);
+        let mut decoder = Decoder::new(std::io::Cursor::new(bytes));
+
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 1 })
+        );
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 2 })
+        );
+        assert_eq!(decoder.next_packet().unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_data_short_final_block() {
+        let mut data = vec![0x00, 0x03, 0x00, 0x01];
+        data.extend_from_slice(b"hello");
+        let mut decoder = Decoder::new(std::io::Cursor::new(data));
+
+        match decoder.next_packet().unwrap().unwrap() {
+            OwnedPacket::Data { block, data, .. } => {
+                assert_eq!(block, 1);
+                assert_eq!(data, b"hello");
+            }
+            other => panic!("did not get expected packet: Data, got {other:?}"),
+        }
+        assert_eq!(decoder.next_packet().unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_data_full_block_bounded_by_blksize() {
+        let mut data = vec![0x00, 0x03, 0x00, 0x01];
+        data.extend(core::iter::repeat_n(0xAB, 512));
+        // A second packet immediately follows; framing must stop at
+        // `blksize` rather than swallowing it.
+        data.extend_from_slice(&[0x00, 0x04, 0x00, 0x09]);
+
+        let mut decoder = Decoder::new(std::io::Cursor::new(data));
+        match decoder.next_packet().unwrap().unwrap() {
+            OwnedPacket::Data { data, .. } => assert_eq!(data.len(), 512),
+            other => panic!("did not get expected packet: Data, got {other:?}"),
+        }
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 9 })
+        );
+    }
+
+    #[test]
+    fn test_packet_split_across_reads_is_reassembled() {
+        let reader = StagedReader::new(vec![vec![0x00, 0x04], vec![0x00, 0x2A]]);
+        let mut decoder = Decoder::new(reader);
+
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 42 })
+        );
+    }
+
+    #[test]
+    fn test_truncated_record_is_distinct_from_parse_error() {
+        // A 3-byte ACK can never be complete: the source ran dry mid
+        // record rather than producing a malformed one.
+        let mut decoder = Decoder::new(std::io::Cursor::new(vec![0x00, 0x04, 0x00]));
+
+        assert!(matches!(
+            decoder.next_packet().unwrap_err(),
+            Error::Truncated { needed: 4, got: 3 }
+        ));
+    }
+
+    #[test]
+    fn test_invalid_opcode_is_a_parse_error() {
+        let mut decoder = Decoder::new(std::io::Cursor::new(vec![0xFF, 0xFF]));
+
+        assert!(matches!(
+            decoder.next_packet().unwrap_err(),
+            Error::Parse(crate::packet::Error::InvalidOpcode)
+        ));
+    }
+
+    #[test]
+    fn test_follow_mode_waits_for_more_bytes_instead_of_eof() {
+        // The ACK's second half only "arrives" on the reader's third
+        // call; a non-follow decoder would see the first call's bytes,
+        // then a zero-byte read and stop. `follow` keeps polling.
+        let reader = StagedReader::new(vec![vec![0x00, 0x04], vec![], vec![0x00, 0x2A]]);
+        let mut decoder = Decoder::new(reader);
+        decoder.follow = true;
+
+        assert_eq!(
+            decoder.next_packet().unwrap(),
+            Some(OwnedPacket::Ack { op_code: 4, block: 42 })
+        );
+    }
+}
@@ -0,0 +1,14 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod cursor;
+
+#[cfg(feature = "std")]
+pub mod codec;
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod decoder;
+pub mod netascii;
+pub mod options;
+pub mod packet;
@@ -0,0 +1,149 @@
+//! RFC 1350 §4 netascii line-ending transcoding.
+//!
+//! netascii always represents a line ending on the wire as `CR LF` and an
+//! embedded `CR` as `CR NUL`, independent of what the local platform uses
+//! for a newline. [`to_netascii`] is a pure, one-shot encode since the
+//! local convention is always expanded the same way. [`from_netascii`]
+//! decodes one DATA block at a time and needs [`CrState`] threaded across
+//! blocks, because a `CR` can land as the very last byte of one block with
+//! its `LF`/`NUL` pair arriving as the first byte of the next.
+
+use alloc::vec::Vec;
+
+const CR: u8 = b'\r';
+const LF: u8 = b'\n';
+const NUL: u8 = 0;
+
+/// Decode-side state carried across consecutive DATA blocks of the same
+/// netascii transfer, recording a trailing `CR` whose pair hasn't arrived
+/// yet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CrState {
+    pending_cr: bool,
+}
+
+/// Encodes local bytes as wire netascii: every `\n` becomes `CR LF`, and
+/// every literal `\r` becomes `CR NUL`.
+pub fn to_netascii(local: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(local.len());
+    for &b in local {
+        match b {
+            LF => out.extend_from_slice(&[CR, LF]),
+            CR => out.extend_from_slice(&[CR, NUL]),
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+/// Decodes one DATA block of wire netascii back to local bytes, reversing
+/// [`to_netascii`]: `CR LF` becomes `\n` and `CR NUL` becomes a literal
+/// `\r`. `state` must be the same [`CrState`] passed to the previous block
+/// of this transfer (default-initialized for the first block), so a `CR`
+/// split across the boundary between two blocks is still resolved.
+pub fn from_netascii(wire: &[u8], state: &mut CrState) -> Vec<u8> {
+    let mut out = Vec::with_capacity(wire.len());
+    let mut iter = wire.iter().copied();
+
+    if state.pending_cr {
+        state.pending_cr = false;
+        match iter.next() {
+            Some(LF) => out.push(LF),
+            Some(NUL) => out.push(CR),
+            Some(other) => {
+                out.push(CR);
+                out.push(other);
+            }
+            None => {
+                // The block ended right after a CR and this one is empty;
+                // stay pending for whatever block comes next.
+                state.pending_cr = true;
+                return out;
+            }
+        }
+    }
+
+    while let Some(b) = iter.next() {
+        if b != CR {
+            out.push(b);
+            continue;
+        }
+
+        match iter.next() {
+            Some(LF) => out.push(LF),
+            Some(NUL) => out.push(CR),
+            Some(other) => {
+                out.push(CR);
+                out.push(other);
+            }
+            None => state.pending_cr = true,
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_netascii_expands_lf_and_cr() {
+        assert_eq!(to_netascii(b"a\nb\rc"), b"a\r\nb\r\0c");
+    }
+
+    #[test]
+    fn test_from_netascii_round_trips() {
+        let local = b"a\nb\rc\n";
+        let wire = to_netascii(local);
+
+        let mut state = CrState::default();
+        assert_eq!(from_netascii(&wire, &mut state), local);
+        assert_eq!(state, CrState::default());
+    }
+
+    #[test]
+    fn test_from_netascii_cr_lf_split_across_blocks() {
+        let local = b"hello\nworld";
+        let wire = to_netascii(local);
+
+        // Split right between the CR and LF of the embedded line ending.
+        let split = wire.iter().position(|&b| b == LF).unwrap();
+        let (first, second) = wire.split_at(split);
+
+        let mut state = CrState::default();
+        let mut decoded = from_netascii(first, &mut state);
+        assert!(state.pending_cr);
+        decoded.extend(from_netascii(second, &mut state));
+
+        assert_eq!(decoded, local);
+        assert_eq!(state, CrState::default());
+    }
+
+    #[test]
+    fn test_from_netascii_bare_cr_split_across_blocks() {
+        // A literal `\r` encodes as CR NUL; split right after the CR.
+        let mut state = CrState::default();
+        let mut decoded = from_netascii(&[b'a', CR], &mut state);
+        assert!(state.pending_cr);
+        decoded.extend(from_netascii(&[NUL, b'b'], &mut state));
+
+        assert_eq!(decoded, b"a\rb");
+        assert_eq!(state, CrState::default());
+    }
+
+    #[test]
+    fn test_from_netascii_cr_pending_across_empty_block() {
+        let mut state = CrState::default();
+        let mut decoded = from_netascii(&[b'x', CR], &mut state);
+        assert!(state.pending_cr);
+
+        // An empty block in between must not lose the pending CR.
+        decoded.extend(from_netascii(&[], &mut state));
+        assert!(state.pending_cr);
+
+        decoded.extend(from_netascii(&[LF], &mut state));
+        assert_eq!(decoded, b"x\n");
+        assert_eq!(state, CrState::default());
+    }
+}
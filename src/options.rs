@@ -0,0 +1,288 @@
+//! RFC 2347/2348/2349 option negotiation for RRQ/WRQ and OACK packets.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::packet::Error;
+
+/// Minimum `blksize` a peer may request or acknowledge (RFC 2348).
+pub const MIN_BLKSIZE: u16 = 8;
+/// Maximum `blksize` a peer may request or acknowledge (RFC 2348).
+pub const MAX_BLKSIZE: u16 = 65464;
+/// Block size used when no `blksize` option is negotiated (RFC 1350).
+pub const DEFAULT_BLKSIZE: u16 = 512;
+
+/// Experimental, non-RFC `compress` option value: the DATA-stream
+/// compression algorithm both peers have agreed to use for this transfer.
+/// See [`crate::compress`] for the `encode`/`decode` hooks this negotiates.
+/// A peer that doesn't recognize `compress` rejects it like any other
+/// unknown option, so the transfer falls back to plain, uncompressed
+/// octet mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Deflate,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Deflate => "deflate",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+impl core::str::FromStr for Compression {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deflate" => Ok(Compression::Deflate),
+            "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The subset of RFC 2347 options this crate understands, appended as
+/// `option\0value\0` pairs after the mode field of an RRQ/WRQ, or echoed
+/// back (possibly narrowed) in an OACK.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// RFC 2348: requested/negotiated DATA block size, in `8..=65464`.
+    pub blksize: Option<u16>,
+    /// RFC 2349: requested/negotiated retransmission timeout, in seconds.
+    pub timeout: Option<u8>,
+    /// RFC 2349: total transfer size in bytes. `0` in a request means
+    /// "please tell me the file size"; the reply carries the real size.
+    pub tsize: Option<u64>,
+    /// Experimental, non-RFC: requested/negotiated DATA-stream compression
+    /// algorithm. Absent from the OACK means the peer rejected it and the
+    /// transfer proceeds uncompressed.
+    pub compress: Option<Compression>,
+}
+
+impl Options {
+    /// True if no option was negotiated, i.e. this packet behaves exactly
+    /// like a plain RFC 1350 peer.
+    pub fn is_empty(&self) -> bool {
+        self.blksize.is_none()
+            && self.timeout.is_none()
+            && self.tsize.is_none()
+            && self.compress.is_none()
+    }
+
+    /// Parses trailing `option\0value\0` pairs. An empty slice is valid and
+    /// yields an empty `Options`.
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Options, Error> {
+        let mut options = Options::default();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            let (name, next) = read_zero_terminated(bytes, pos)?;
+            let (value, next) = read_zero_terminated(bytes, next)?;
+            pos = next;
+
+            match name.to_lowercase().as_str() {
+                "blksize" => {
+                    let blksize = parse_option_value::<u16>("blksize", value)?;
+                    if !(MIN_BLKSIZE..=MAX_BLKSIZE).contains(&blksize) {
+                        return Err(Error::InvalidOptionValue {
+                            option: "blksize",
+                            value: value.to_string(),
+                        });
+                    }
+                    options.blksize = Some(blksize);
+                }
+                "timeout" => {
+                    let timeout = parse_option_value::<u8>("timeout", value)?;
+                    if timeout == 0 {
+                        return Err(Error::InvalidOptionValue {
+                            option: "timeout",
+                            value: value.to_string(),
+                        });
+                    }
+                    options.timeout = Some(timeout);
+                }
+                "tsize" => {
+                    options.tsize = Some(parse_option_value::<u64>("tsize", value)?);
+                }
+                "compress" => {
+                    options.compress = Some(parse_option_value::<Compression>("compress", value)?);
+                }
+                // RFC 2347: an option this peer doesn't support is simply
+                // left out of the reply, not a reason to reject the whole
+                // packet.
+                _ => {}
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Appends this option set's wire representation (in `blksize`,
+    /// `timeout`, `tsize`, `compress` order) to `buf`. Writes nothing if
+    /// empty.
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        if let Some(blksize) = self.blksize {
+            encode_pair(buf, "blksize", &blksize.to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            encode_pair(buf, "timeout", &timeout.to_string());
+        }
+        if let Some(tsize) = self.tsize {
+            encode_pair(buf, "tsize", &tsize.to_string());
+        }
+        if let Some(compress) = self.compress {
+            encode_pair(buf, "compress", compress.as_str());
+        }
+    }
+}
+
+fn encode_pair(buf: &mut Vec<u8>, name: &str, value: &str) {
+    buf.extend_from_slice(name.as_bytes());
+    buf.push(0);
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(0);
+}
+
+fn parse_option_value<T: core::str::FromStr>(option: &'static str, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::InvalidOptionValue {
+        option,
+        value: value.to_string(),
+    })
+}
+
+/// Reads a UTF-8 string starting at `start`, up to (and consuming) the next
+/// zero byte, returning the string and the position just past it.
+fn read_zero_terminated(bytes: &[u8], start: usize) -> Result<(&str, usize), Error> {
+    let end = bytes[start..]
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or(Error::NoZeroByte)?
+        + start;
+
+    let s = core::str::from_utf8(&bytes[start..end]).map_err(|_| Error::InvalidUtf8)?;
+    Ok((s, end + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_empty_is_empty() {
+        assert_eq!(Options::parse(&[]).unwrap(), Options::default());
+    }
+
+    #[test]
+    fn test_parse_blksize() {
+        let options = Options::parse(b"blksize\x001024\0").unwrap();
+        assert_eq!(options.blksize, Some(1024));
+    }
+
+    #[test]
+    fn test_parse_is_case_insensitive() {
+        let options = Options::parse(b"BlkSize\x001024\0").unwrap();
+        assert_eq!(options.blksize, Some(1024));
+    }
+
+    #[test]
+    fn test_parse_blksize_out_of_range() {
+        assert!(matches!(
+            Options::parse(b"blksize\x004\0").unwrap_err(),
+            Error::InvalidOptionValue { option: "blksize", .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_timeout_and_tsize() {
+        let options = Options::parse(b"timeout\x005\0tsize\x0010000\0").unwrap();
+        assert_eq!(options.timeout, Some(5));
+        assert_eq!(options.tsize, Some(10000));
+    }
+
+    #[test]
+    fn test_parse_tsize_zero_means_please_tell_me() {
+        let options = Options::parse(b"tsize\x000\0").unwrap();
+        assert_eq!(options.tsize, Some(0));
+    }
+
+    #[test]
+    fn test_parse_unknown_option_is_silently_skipped() {
+        // RFC 2347: an option this peer doesn't recognize is left out of
+        // the reply, not a reason to reject the whole packet.
+        let options = Options::parse(b"windowsize\x004\0").unwrap();
+        assert_eq!(options, Options::default());
+    }
+
+    #[test]
+    fn test_parse_unknown_option_alongside_known_ones() {
+        let options =
+            Options::parse(b"windowsize\x004\0blksize\x001024\0").unwrap();
+        assert_eq!(options.blksize, Some(1024));
+    }
+
+    #[test]
+    fn test_encode_round_trips() {
+        let options = Options {
+            blksize: Some(1024),
+            timeout: Some(3),
+            tsize: Some(42),
+            compress: Some(Compression::Zstd),
+        };
+
+        let mut buf = Vec::new();
+        options.encode(&mut buf);
+
+        assert_eq!(Options::parse(&buf).unwrap(), options);
+    }
+
+    #[test]
+    fn test_parse_compress() {
+        let options = Options::parse(b"compress\0deflate\0").unwrap();
+        assert_eq!(options.compress, Some(Compression::Deflate));
+    }
+
+    #[test]
+    fn test_parse_compress_is_case_insensitive() {
+        let options = Options::parse(b"compress\0GZIP\0").unwrap();
+        assert_eq!(options.compress, Some(Compression::Gzip));
+    }
+
+    #[test]
+    fn test_parse_compress_unknown_algorithm() {
+        assert!(matches!(
+            Options::parse(b"compress\0lzma\0").unwrap_err(),
+            Error::InvalidOptionValue { option: "compress", .. }
+        ));
+    }
+
+    #[test]
+    fn test_compress_omitted_from_oack_means_rejected() {
+        // A server that doesn't support compression simply drops the
+        // option from its OACK instead of echoing it back.
+        let requested = Options {
+            compress: Some(Compression::Zstd),
+            ..Default::default()
+        };
+        let oack = Options::default();
+
+        assert_ne!(requested, oack);
+        assert!(oack.compress.is_none());
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(Options::default().is_empty());
+        assert!(!Options {
+            blksize: Some(512),
+            ..Default::default()
+        }
+        .is_empty());
+    }
+}
@@ -1,4 +1,8 @@
-use std::io::{BufReader, Cursor, Read};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::cursor::ByteCursor;
+use crate::options::Options;
 
 #[derive(Debug, PartialEq)]
 pub enum Mode {
@@ -7,52 +11,118 @@ pub enum Mode {
     Mail,
 }
 
+impl<'a> TryFrom<&'a str> for Mode {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        match s.to_lowercase().as_str() {
+            "netascii" => Ok(Mode::NetAscii),
+            "octet" => Ok(Mode::Octet),
+            "mail" => Ok(Mode::Mail),
+            _ => Err(Error::InvalidMode(s.to_string())),
+        }
+    }
+}
+
+impl Mode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Mode::NetAscii => "netascii",
+            Mode::Octet => "octet",
+            Mode::Mail => "mail",
+        }
+    }
+
+    /// Encodes a DATA payload about to be sent under this mode. `octet` and
+    /// `mail` pass `local` through unchanged; `netascii` expands line
+    /// endings per RFC 1350 §4 (see [`crate::netascii::to_netascii`]).
+    pub fn encode_data(&self, local: &[u8]) -> Vec<u8> {
+        match self {
+            Mode::NetAscii => crate::netascii::to_netascii(local),
+            Mode::Octet | Mode::Mail => local.to_vec(),
+        }
+    }
+
+    /// Decodes one received DATA payload under this mode, reversing
+    /// [`Mode::encode_data`]. `state` must be the same
+    /// [`crate::netascii::CrState`] passed for the previous block of this
+    /// transfer so a split `CR` is resolved across block boundaries; it is
+    /// ignored for `octet`/`mail`.
+    pub fn decode_data(&self, wire: &[u8], state: &mut crate::netascii::CrState) -> Vec<u8> {
+        match self {
+            Mode::NetAscii => crate::netascii::from_netascii(wire, state),
+            Mode::Octet | Mode::Mail => wire.to_vec(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     InvalidOpcode,
     NoZeroByte,
+    /// Not enough bytes remained in the buffer to read the next field.
+    Truncated { needed: usize, got: usize },
+    /// The mode string in an RRQ/WRQ was not one of "netascii", "octet" or "mail".
+    InvalidMode(String),
+    /// A string field contained bytes that are not valid UTF-8.
+    InvalidUtf8,
+    /// An ERROR packet's error code was outside the range defined by RFC 1350.
+    BadErrorCode(u16),
+    /// A recognized option's value was malformed or outside its valid range.
+    InvalidOptionValue { option: &'static str, value: String },
+    /// A DATA payload was larger than [`crate::options::MAX_BLKSIZE`], the
+    /// largest `blksize` RFC 2348 allows. `Packet::parse` does not know the
+    /// negotiated `blksize`, so payloads under that bound are not checked
+    /// against it.
+    PayloadTooLarge { max: usize, got: usize },
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::InvalidOpcode => write!(f, "invalid opcode"),
             Error::NoZeroByte => write!(f, "couldn't find zero byte"),
+            Error::Truncated { needed, got } => {
+                write!(f, "truncated packet: needed {needed} bytes, got {got}")
+            }
+            Error::InvalidMode(mode) => write!(f, "invalid mode: {mode:?}"),
+            Error::InvalidUtf8 => write!(f, "field is not valid UTF-8"),
+            Error::BadErrorCode(code) => write!(f, "invalid error code: {code}"),
+            Error::InvalidOptionValue { option, value } => {
+                write!(f, "invalid value for option {option:?}: {value:?}")
+            }
+            Error::PayloadTooLarge { max, got } => {
+                write!(f, "DATA payload too large: max {max} bytes, got {got}")
+            }
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
-impl From<&str> for Mode {
-    fn from(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "netascii" => Mode::NetAscii,
-            "octet" => Mode::Octet,
-            "mail" => Mode::Mail,
-            _ => panic!(),
-        }
-    }
-}
-
 const READ_OPCODE: u16 = 1;
 const WRITE_OPCODE: u16 = 2;
 const DATA_OPCODE: u16 = 3;
 const ACK_OPCODE: u16 = 4;
 const ERROR_OPCODE: u16 = 5;
+const OACK_OPCODE: u16 = 6;
 
 /// https://www.rfc-editor.org/rfc/rfc1350
+#[derive(Debug)]
 pub enum Packet<'a> {
     /// RRQ/WRQ Packet
     ///  2 bytes     string    1 byte     string   1 byte
     ///  ------------------------------------------------
     /// | Opcode |  Filename  |   0  |    Mode    |   0  |
     ///  ------------------------------------------------
-    /// Mode can be either "netascii", "octet" or "mail"
+    /// Mode can be either "netascii", "octet" or "mail". May be followed by
+    /// RFC 2347 `option\0value\0` pairs (see [`Options`]).
     Request {
         op_code: u16,
         file_name: &'a str,
         mode: Mode,
+        options: Options,
     },
     /// DATA Packet
     ///  2 bytes     2 bytes      n bytes
@@ -60,14 +130,12 @@ pub enum Packet<'a> {
     /// | Opcode |   Block #  |   Data     |
     ///  ----------------------------------
     /// The block numbers on data packets begin with one and increase by one for
-    /// each new block of data.
+    /// each new block of data. A block shorter than the negotiated `blksize`
+    /// (512 by default) marks the last block of the transfer.
     Data {
         op_code: u16,
         block: u16,
-        data: [u8; 512],
-
-        // If its less than 512 bytes, it's the last data packet
-        len: usize,
+        data: Vec<u8>,
     },
     /// ACK Packet
     ///  2 bytes     2 bytes
@@ -96,10 +164,19 @@ pub enum Packet<'a> {
         error_code: u16,
         error_msg: &'a str,
     },
+    /// OACK Packet (RFC 2347)
+    ///  2 bytes   string    1 byte   string    1 byte
+    ///  ------------------------------------------------
+    /// | Opcode | OptionName |  0  | OptionValue |  0  | ...
+    ///  ------------------------------------------------
+    /// Sent by the server to confirm the subset of requested options it
+    /// will honor; any option it omits is not in effect for the transfer.
+    OAck { op_code: u16, options: Options },
 }
 
 impl<'a> Packet<'a> {
     pub fn parse(bytes: &'a [u8]) -> Result<Packet<'a>, Error> {
+        require_len(bytes, 2)?;
         let op_code = u16::from_be_bytes([bytes[0], bytes[1]]);
 
         let packet = match op_code {
@@ -108,47 +185,250 @@ impl<'a> Packet<'a> {
             DATA_OPCODE => parse_data(bytes)?,
             ACK_OPCODE => parse_ack(bytes)?,
             ERROR_OPCODE => parse_error(bytes)?,
-            _ => Err(Error::InvalidOpcode)?,
+            OACK_OPCODE => parse_oack(bytes)?,
+            _ => return Err(Error::InvalidOpcode),
         };
 
         Ok(packet)
     }
+
+    /// Serializes this packet into its RFC 1350 wire representation, appending to `buf`.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Packet::Request {
+                op_code,
+                file_name,
+                mode,
+                options,
+            } => encode_request(buf, *op_code, file_name, mode, options),
+            Packet::Data {
+                op_code,
+                block,
+                data,
+            } => encode_data(buf, *op_code, *block, data),
+            Packet::Ack { op_code, block } => encode_ack(buf, *op_code, *block),
+            Packet::Error {
+                op_code,
+                error_code,
+                error_msg,
+            } => encode_error(buf, *op_code, *error_code, error_msg),
+            Packet::OAck { op_code, options } => encode_oack(buf, *op_code, options),
+        }
+    }
+
+    /// Serializes this packet into its RFC 1350 wire representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf
+    }
+
+    /// Copies this packet's borrowed fields into an [`OwnedPacket`] that
+    /// does not borrow from the input buffer.
+    pub fn into_owned(self) -> OwnedPacket {
+        match self {
+            Packet::Request {
+                op_code,
+                file_name,
+                mode,
+                options,
+            } => OwnedPacket::Request {
+                op_code,
+                file_name: file_name.to_string(),
+                mode,
+                options,
+            },
+            Packet::Data {
+                op_code,
+                block,
+                data,
+            } => OwnedPacket::Data { op_code, block, data },
+            Packet::Ack { op_code, block } => OwnedPacket::Ack { op_code, block },
+            Packet::Error {
+                op_code,
+                error_code,
+                error_msg,
+            } => OwnedPacket::Error {
+                op_code,
+                error_code,
+                error_msg: error_msg.to_string(),
+            },
+            Packet::OAck { op_code, options } => OwnedPacket::OAck { op_code, options },
+        }
+    }
+}
+
+/// An owned counterpart of [`Packet`] that does not borrow from the input
+/// buffer, so it can outlive the buffer it was decoded from (e.g. inside a
+/// [`crate::codec::TftpCodec`] that reuses its read buffer across decodes).
+#[derive(Debug, PartialEq)]
+pub enum OwnedPacket {
+    Request {
+        op_code: u16,
+        file_name: String,
+        mode: Mode,
+        options: Options,
+    },
+    Data {
+        op_code: u16,
+        block: u16,
+        data: Vec<u8>,
+    },
+    Ack {
+        op_code: u16,
+        block: u16,
+    },
+    Error {
+        op_code: u16,
+        error_code: u16,
+        error_msg: String,
+    },
+    OAck {
+        op_code: u16,
+        options: Options,
+    },
+}
+
+impl OwnedPacket {
+    /// Serializes this packet into its RFC 1350 wire representation, appending to `buf`.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            OwnedPacket::Request {
+                op_code,
+                file_name,
+                mode,
+                options,
+            } => encode_request(buf, *op_code, file_name, mode, options),
+            OwnedPacket::Data {
+                op_code,
+                block,
+                data,
+            } => encode_data(buf, *op_code, *block, data),
+            OwnedPacket::Ack { op_code, block } => encode_ack(buf, *op_code, *block),
+            OwnedPacket::Error {
+                op_code,
+                error_code,
+                error_msg,
+            } => encode_error(buf, *op_code, *error_code, error_msg),
+            OwnedPacket::OAck { op_code, options } => encode_oack(buf, *op_code, options),
+        }
+    }
+
+    /// Serializes this packet into its RFC 1350 wire representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf
+    }
+}
+
+impl<'a> From<Packet<'a>> for OwnedPacket {
+    fn from(packet: Packet<'a>) -> Self {
+        packet.into_owned()
+    }
+}
+
+fn encode_request(
+    buf: &mut Vec<u8>,
+    op_code: u16,
+    file_name: &str,
+    mode: &Mode,
+    options: &Options,
+) {
+    buf.extend_from_slice(&op_code.to_be_bytes());
+    buf.extend_from_slice(file_name.as_bytes());
+    buf.push(0);
+    buf.extend_from_slice(mode.as_str().as_bytes());
+    buf.push(0);
+    options.encode(buf);
+}
+
+fn encode_oack(buf: &mut Vec<u8>, op_code: u16, options: &Options) {
+    buf.extend_from_slice(&op_code.to_be_bytes());
+    options.encode(buf);
+}
+
+fn encode_data(buf: &mut Vec<u8>, op_code: u16, block: u16, data: &[u8]) {
+    buf.extend_from_slice(&op_code.to_be_bytes());
+    buf.extend_from_slice(&block.to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn encode_ack(buf: &mut Vec<u8>, op_code: u16, block: u16) {
+    buf.extend_from_slice(&op_code.to_be_bytes());
+    buf.extend_from_slice(&block.to_be_bytes());
+}
+
+fn encode_error(buf: &mut Vec<u8>, op_code: u16, error_code: u16, error_msg: &str) {
+    buf.extend_from_slice(&op_code.to_be_bytes());
+    buf.extend_from_slice(&error_code.to_be_bytes());
+    buf.extend_from_slice(error_msg.as_bytes());
+    buf.push(0);
+}
+
+/// Returns `Error::Truncated` if `bytes` is shorter than `needed`.
+fn require_len(bytes: &[u8], needed: usize) -> Result<(), Error> {
+    if bytes.len() < needed {
+        return Err(Error::Truncated {
+            needed,
+            got: bytes.len(),
+        });
+    }
+    Ok(())
 }
 
-fn parse_rwrq(bytes: &[u8], op_code: u16) -> Result<Packet, Error> {
-    let mut cursor = Cursor::new(&bytes[2..]);
+fn parse_rwrq(bytes: &[u8], op_code: u16) -> Result<Packet<'_>, Error> {
+    let mut cursor = ByteCursor::new(&bytes[2..]);
 
     let file_name = read_until_zero_byte(&mut cursor)?;
-    let file_name = std::str::from_utf8(file_name).unwrap();
+    let file_name = core::str::from_utf8(file_name).map_err(|_| Error::InvalidUtf8)?;
 
     let mode = read_until_zero_byte(&mut cursor)?;
-    let mode = std::str::from_utf8(mode).unwrap();
-    let mode: Mode = mode.into();
+    let mode = core::str::from_utf8(mode).map_err(|_| Error::InvalidUtf8)?;
+    let mode: Mode = mode.try_into()?;
+
+    let remaining = &cursor.get_ref()[cursor.position()..];
+    let options = Options::parse(remaining)?;
 
     Ok(Packet::Request {
         op_code,
         file_name,
         mode,
+        options,
     })
 }
 
-fn parse_data(bytes: &[u8]) -> Result<Packet, Error> {
+fn parse_data(bytes: &[u8]) -> Result<Packet<'_>, Error> {
+    require_len(bytes, 4)?;
     let block = u16::from_be_bytes([bytes[2], bytes[3]]);
 
-    let mut data = [0; 512];
-    let mut reader = BufReader::new(&bytes[4..]);
-    // TODO: handle error
-    let len = reader.read(&mut data).expect("ok");
+    let payload = &bytes[4..];
+    if payload.len() > crate::options::MAX_BLKSIZE as usize {
+        return Err(Error::PayloadTooLarge {
+            max: crate::options::MAX_BLKSIZE as usize,
+            got: payload.len(),
+        });
+    }
 
     Ok(Packet::Data {
         op_code: DATA_OPCODE,
         block,
-        data,
-        len,
+        data: payload.to_vec(),
     })
 }
 
-fn parse_ack(bytes: &[u8]) -> Result<Packet, Error> {
+fn parse_oack(bytes: &[u8]) -> Result<Packet<'_>, Error> {
+    require_len(bytes, 2)?;
+    let options = Options::parse(&bytes[2..])?;
+
+    Ok(Packet::OAck {
+        op_code: OACK_OPCODE,
+        options,
+    })
+}
+
+fn parse_ack(bytes: &[u8]) -> Result<Packet<'_>, Error> {
+    require_len(bytes, 4)?;
     let block = u16::from_be_bytes([bytes[2], bytes[3]]);
 
     Ok(Packet::Ack {
@@ -157,13 +437,17 @@ fn parse_ack(bytes: &[u8]) -> Result<Packet, Error> {
     })
 }
 
-fn parse_error(bytes: &[u8]) -> Result<Packet, Error> {
+fn parse_error(bytes: &[u8]) -> Result<Packet<'_>, Error> {
+    require_len(bytes, 4)?;
     let error_code = u16::from_be_bytes([bytes[2], bytes[3]]);
+    if error_code > 7 {
+        return Err(Error::BadErrorCode(error_code));
+    }
 
-    let mut cursor = Cursor::new(&bytes[4..]);
+    let mut cursor = ByteCursor::new(&bytes[4..]);
 
     let error_msg = read_until_zero_byte(&mut cursor)?;
-    let error_msg = std::str::from_utf8(error_msg).unwrap();
+    let error_msg = core::str::from_utf8(error_msg).map_err(|_| Error::InvalidUtf8)?;
 
     Ok(Packet::Error {
         op_code: ERROR_OPCODE,
@@ -172,13 +456,20 @@ fn parse_error(bytes: &[u8]) -> Result<Packet, Error> {
     })
 }
 
-fn read_until_zero_byte<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
-    let start = cursor.position() as usize;
-    let end = cursor.get_ref().len() - 1;
+fn read_until_zero_byte<'a>(cursor: &mut ByteCursor<'a>) -> Result<&'a [u8], Error> {
+    let start = cursor.position();
+    let len = cursor.get_ref().len();
 
-    for i in start..end {
+    if start >= len {
+        return Err(Error::Truncated {
+            needed: start + 1,
+            got: len,
+        });
+    }
+
+    for i in start..len {
         if cursor.get_ref()[i] == b'\0' {
-            cursor.set_position((i + 1) as u64);
+            cursor.set_position(i + 1);
 
             return Ok(&cursor.get_ref()[start..i]);
         }
@@ -191,7 +482,7 @@ fn read_until_zero_byte<'a>(cursor: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], E
 mod test {
     use crate::packet::ERROR_OPCODE;
 
-    use super::{Mode, Packet, ACK_OPCODE, DATA_OPCODE, READ_OPCODE, WRITE_OPCODE};
+    use super::{Error, Mode, Packet, ACK_OPCODE, DATA_OPCODE, READ_OPCODE, WRITE_OPCODE};
 
     fn test_rwrq(rq: &[u8], exp_op_code: u16, exp_file_name: &str, exp_mode: Mode) {
         let packet = Packet::parse(rq).unwrap();
@@ -201,7 +492,9 @@ mod test {
                 op_code,
                 file_name,
                 mode,
+                options,
             } => {
+                assert!(options.is_empty());
                 assert_eq!(
                     op_code, exp_op_code,
                     "Expected: {}\nGot: {}",
@@ -223,7 +516,7 @@ mod test {
         // read, main.rs, netascii
         let rrq = &[
             0x00, 0x01, 0x6D, 0x61, 0x69, 0x6E, 0x2E, 0x72, 0x73, 0x00, 0x6E, 0x65, 0x74, 0x61,
-            0x73, 0x63, 0x69, 0x69, 0x00, /**/ 0x00,
+            0x73, 0x63, 0x69, 0x69, 0x00,
         ];
 
         test_rwrq(rrq, READ_OPCODE, "main.rs", Mode::NetAscii);
@@ -234,7 +527,7 @@ mod test {
         // read, main.rs, netascii
         let wrq = &[
             0x00, 0x02, 0x6D, 0x61, 0x69, 0x6E, 0x2E, 0x72, 0x73, 0x00, 0x6E, 0x65, 0x74, 0x61,
-            0x73, 0x63, 0x69, 0x69, 0x00, /**/ 0x00,
+            0x73, 0x63, 0x69, 0x69, 0x00,
         ];
 
         test_rwrq(wrq, WRITE_OPCODE, "main.rs", Mode::NetAscii);
@@ -254,12 +547,10 @@ mod test {
                 op_code,
                 block,
                 data,
-                len,
             } => {
                 assert_eq!(op_code, DATA_OPCODE);
                 assert_eq!(block, 0);
-                assert_eq!(&data[0..11], b"hello world");
-                assert_eq!(len, 11);
+                assert_eq!(data, b"hello world");
             }
             _ => panic!("did not get expected packet: Data"),
         }
@@ -301,4 +592,242 @@ mod test {
             _ => panic!("did not get expected packet: Error"),
         }
     }
+
+    #[test]
+    fn test_parse_empty_buffer_is_err() {
+        assert!(matches!(
+            Packet::parse(&[]).unwrap_err(),
+            Error::Truncated { needed: 2, got: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_parse_truncated_ack() {
+        let data = &[0x00, 0x04, 0x00];
+        assert!(matches!(
+            Packet::parse(data).unwrap_err(),
+            Error::Truncated { needed: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_truncated_error() {
+        let data = &[0x00, 0x05, 0x00];
+        assert!(matches!(
+            Packet::parse(data).unwrap_err(),
+            Error::Truncated { needed: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_rwrq_missing_mode() {
+        // opcode + filename + zero byte, but no mode string at all
+        let rq = &[0x00, 0x01, 0x61, 0x00];
+        assert!(matches!(
+            Packet::parse(rq).unwrap_err(),
+            Error::Truncated { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_rwrq_no_zero_byte() {
+        let rq = &[0x00, 0x01, 0x61, 0x62, 0x63];
+        assert!(matches!(Packet::parse(rq).unwrap_err(), Error::NoZeroByte));
+    }
+
+    #[test]
+    fn test_parse_rwrq_invalid_mode() {
+        let rq = &[0x00, 0x01, 0x61, 0x00, 0x62, 0x61, 0x64, 0x00];
+        assert!(matches!(
+            Packet::parse(rq).unwrap_err(),
+            Error::InvalidMode(m) if m == "bad"
+        ));
+    }
+
+    #[test]
+    fn test_parse_rwrq_invalid_utf8() {
+        let rq = &[0x00, 0x01, 0xFF, 0xFE, 0x00, 0x6F, 0x63, 0x74, 0x65, 0x74, 0x00];
+        assert!(matches!(
+            Packet::parse(rq).unwrap_err(),
+            Error::InvalidUtf8
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_opcode() {
+        let data = &[0xFF, 0xFF];
+        assert!(matches!(
+            Packet::parse(data).unwrap_err(),
+            Error::InvalidOpcode
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_bad_code() {
+        let data = &[0x00, 0x05, 0x00, 0xFF, 0x00];
+        assert!(matches!(
+            Packet::parse(data).unwrap_err(),
+            Error::BadErrorCode(255)
+        ));
+    }
+
+    #[test]
+    fn test_encode_rrq_round_trips() {
+        let rrq = &[
+            0x00, 0x01, 0x6D, 0x61, 0x69, 0x6E, 0x2E, 0x72, 0x73, 0x00, 0x6E, 0x65, 0x74, 0x61,
+            0x73, 0x63, 0x69, 0x69, 0x00,
+        ];
+
+        let packet = Packet::parse(rrq).unwrap();
+        assert_eq!(packet.to_bytes(), rrq);
+    }
+
+    #[test]
+    fn test_encode_data_round_trips() {
+        let data = &[
+            0x00, 0x03, 0x00, 0x01, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x77, 0x6F, 0x72, 0x6C,
+            0x64,
+        ];
+
+        let packet = Packet::parse(data).unwrap();
+        assert_eq!(packet.to_bytes(), data);
+    }
+
+    #[test]
+    fn test_netascii_mode_encode_decode_data_round_trips_through_packet() {
+        // Exercises Mode::encode_data/decode_data actually wired into a
+        // Packet::Data payload, not just the standalone netascii functions.
+        let local: &[u8] = b"hello\nworld\r!";
+        let mode = Mode::NetAscii;
+
+        let packet = Packet::Data {
+            op_code: DATA_OPCODE,
+            block: 1,
+            data: mode.encode_data(local),
+        };
+
+        let bytes = packet.to_bytes();
+        let parsed = Packet::parse(&bytes).unwrap();
+        match parsed {
+            Packet::Data { data, .. } => {
+                let mut state = crate::netascii::CrState::default();
+                assert_eq!(mode.decode_data(&data, &mut state), local);
+            }
+            _ => panic!("did not get expected packet: Data"),
+        }
+    }
+
+    #[test]
+    fn test_octet_mode_encode_decode_data_is_pass_through() {
+        let local: &[u8] = b"\x00\x01raw bytes\xFF";
+        let mode = Mode::Octet;
+
+        let packet = Packet::Data {
+            op_code: DATA_OPCODE,
+            block: 1,
+            data: mode.encode_data(local),
+        };
+        assert_eq!(packet.to_bytes()[4..], *local);
+
+        let mut state = crate::netascii::CrState::default();
+        assert_eq!(mode.decode_data(local, &mut state), local);
+    }
+
+    #[test]
+    fn test_encode_ack_round_trips() {
+        let ack = &[0x00, 0x04, 0x00, 0x2A];
+
+        let packet = Packet::parse(ack).unwrap();
+        assert_eq!(packet.to_bytes(), ack);
+    }
+
+    #[test]
+    fn test_encode_error_round_trips() {
+        let error = &[0x00, 0x05, 0x00, 0x01, 0x6E, 0x6F, 0x70, 0x65, 0x00];
+
+        let packet = Packet::parse(error).unwrap();
+        assert_eq!(packet.to_bytes(), error);
+    }
+
+    #[test]
+    fn test_into_owned_round_trips_through_bytes() {
+        let data = &[
+            0x00, 0x03, 0x00, 0x01, 0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x20, 0x77, 0x6F, 0x72, 0x6C,
+            0x64,
+        ];
+
+        let owned = Packet::parse(data).unwrap().into_owned();
+        assert_eq!(owned.to_bytes(), data);
+    }
+
+    #[test]
+    fn test_parse_data_oversized_payload_is_err() {
+        let mut data = vec![0x00, 0x03, 0x00, 0x00];
+        data.extend(std::iter::repeat_n(0u8, 65465));
+        assert!(matches!(
+            Packet::parse(&data).unwrap_err(),
+            Error::PayloadTooLarge { max: 65464, got: 65465 }
+        ));
+    }
+
+    #[test]
+    fn test_parse_rrq_with_options() {
+        // read, a, octet, blksize=1024, tsize=0
+        let mut rrq = vec![0x00, 0x01];
+        rrq.extend_from_slice(b"a\0octet\0blksize\x001024\0tsize\x000\0");
+
+        let packet = Packet::parse(&rrq).unwrap();
+        match packet {
+            Packet::Request {
+                file_name, options, ..
+            } => {
+                assert_eq!(file_name, "a");
+                assert_eq!(options.blksize, Some(1024));
+                assert_eq!(options.tsize, Some(0));
+                assert_eq!(options.timeout, None);
+            }
+            _ => panic!("did not get expected packet: Request"),
+        }
+    }
+
+    #[test]
+    fn test_encode_rrq_with_options_round_trips() {
+        let mut rrq = vec![0x00, 0x01];
+        rrq.extend_from_slice(b"a\0octet\0blksize\x001024\0");
+
+        let packet = Packet::parse(&rrq).unwrap();
+        assert_eq!(packet.to_bytes(), rrq);
+    }
+
+    #[test]
+    fn test_parse_and_encode_oack_round_trips() {
+        let mut oack = vec![0x00, 0x06];
+        oack.extend_from_slice(b"blksize\x001024\0tsize\x0042\0");
+
+        let packet = Packet::parse(&oack).unwrap();
+        match &packet {
+            Packet::OAck { op_code, options } => {
+                assert_eq!(*op_code, 6);
+                assert_eq!(options.blksize, Some(1024));
+                assert_eq!(options.tsize, Some(42));
+            }
+            _ => panic!("did not get expected packet: OAck"),
+        }
+
+        assert_eq!(packet.to_bytes(), oack);
+    }
+
+    #[test]
+    fn test_parse_rwrq_unknown_option_is_silently_skipped() {
+        // RFC 2347: an unrecognized option must not fail the whole request;
+        // it's simply left out of the negotiated `Options`.
+        let rq = &[
+            0x00, 0x01, 0x61, 0x00, 0x6F, 0x63, 0x74, 0x65, 0x74, 0x00, 0x77, 0x69, 0x6E, 0x00,
+            0x31, 0x00,
+        ];
+        match Packet::parse(rq).unwrap() {
+            Packet::Request { options, .. } => assert!(options.is_empty()),
+            _ => panic!("did not get expected packet: Request"),
+        }
+    }
 }